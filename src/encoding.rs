@@ -0,0 +1,69 @@
+use std::borrow::Cow;
+
+/// Byte encodings we know how to decode uploaded text as.
+///
+/// Exported spreadsheets and legacy label files are frequently CP437 or
+/// Latin-1 rather than UTF-8; picking the wrong one silently mangles
+/// accented characters instead of erroring.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Encoding {
+    #[default]
+    Utf8,
+    Cp437,
+}
+
+impl Encoding {
+    /// Parse the value of an HTML form field (e.g. a `<select name="encoding">`),
+    /// defaulting to UTF-8 for anything unrecognized.
+    pub fn from_form_value(value: &str) -> Self {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "cp437" => Encoding::Cp437,
+            _ => Encoding::Utf8,
+        }
+    }
+
+    /// Decode `bytes` according to this encoding.
+    pub fn encode<'a>(&self, bytes: &'a [u8]) -> Cow<'a, str> {
+        match self {
+            Encoding::Utf8 => String::from_utf8_lossy(bytes),
+            Encoding::Cp437 => Cow::Owned(bytes.iter().map(|&b| cp437_to_char(b)).collect()),
+        }
+    }
+}
+
+fn cp437_to_char(byte: u8) -> char {
+    if byte < 0x80 {
+        byte as char
+    } else {
+        CP437_UPPER_HALF[(byte - 0x80) as usize]
+    }
+}
+
+/// CP437 code points for bytes 0x80..=0xFF, in order.
+const CP437_UPPER_HALF: [char; 128] = [
+    'Ç', 'ü', 'é', 'â', 'ä', 'à', 'å', 'ç', 'ê', 'ë', 'è', 'ï', 'î', 'ì', 'Ä', 'Å', 'É', 'æ', 'Æ',
+    'ô', 'ö', 'ò', 'û', 'ù', 'ÿ', 'Ö', 'Ü', '¢', '£', '¥', '₧', 'ƒ', 'á', 'í', 'ó', 'ú', 'ñ', 'Ñ',
+    'ª', 'º', '¿', '⌐', '¬', '½', '¼', '¡', '«', '»', '░', '▒', '▓', '│', '┤', '╡', '╢', '╖', '╕',
+    '╣', '║', '╗', '╝', '╜', '╛', '┐', '└', '┴', '┬', '├', '─', '┼', '╞', '╟', '╚', '╔', '╩', '╦',
+    '╠', '═', '╬', '╧', '╨', '╤', '╥', '╙', '╘', '╒', '╓', '╫', '╪', '┘', '┌', '█', '▄', '▌', '▐',
+    '▀', 'α', 'ß', 'Γ', 'π', 'Σ', 'σ', 'µ', 'τ', 'Φ', 'Θ', 'Ω', 'δ', '∞', 'φ', 'ε', '∩', '≡', '±',
+    '≥', '≤', '⌠', '⌡', '÷', '≈', '°', '∙', '·', '√', 'ⁿ', '²', '■', '\u{00a0}',
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pins_known_cp437_code_points() {
+        assert_eq!(cp437_to_char(0x80), 'Ç');
+        assert_eq!(cp437_to_char(0x9B), '¢');
+        assert_eq!(cp437_to_char(0xE1), 'ß');
+        assert_eq!(cp437_to_char(0xFF), '\u{00a0}');
+    }
+
+    #[test]
+    fn cp437_decodes_ascii_bytes_unchanged() {
+        assert_eq!(Encoding::Cp437.encode(b"hammer"), "hammer");
+    }
+}
@@ -0,0 +1,151 @@
+use std::io;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Item {
+    pub name: String,
+    pub quantity: i32,
+}
+
+/// Load every item currently recorded at `path`.
+///
+/// Returns an empty vector if the file does not exist yet (e.g. on first run).
+pub fn read_items(path: &str) -> io::Result<Vec<Item>> {
+    if !Path::new(path).exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut reader = csv::Reader::from_path(path)?;
+    reader
+        .deserialize()
+        .collect::<Result<Vec<Item>, _>>()
+        .map_err(csv_err_to_io)
+}
+
+/// Merge `new_items` into whatever is already recorded at `path`, folding
+/// together rows that share a name by summing their quantities, and
+/// rewrite the file in full (header + one row per distinct item).
+///
+/// Returns the post-merge totals for just the names present in
+/// `new_items`, so callers can show an up-to-date running total without
+/// re-reading the whole file.
+pub fn append_items_to_csv(path: &str, new_items: &[Item]) -> io::Result<Vec<Item>> {
+    let mut existing = read_items(path)?;
+
+    let mut folded: Vec<Item> = Vec::new();
+    for item in new_items {
+        fold_into(&mut folded, item);
+    }
+    for item in &folded {
+        fold_into(&mut existing, item);
+    }
+
+    write_items(path, &existing)?;
+
+    Ok(folded
+        .into_iter()
+        .map(|item| {
+            let quantity = existing
+                .iter()
+                .find(|i| i.name == item.name)
+                .map(|i| i.quantity)
+                .unwrap_or(item.quantity);
+            Item { quantity, ..item }
+        })
+        .collect())
+}
+
+fn fold_into(items: &mut Vec<Item>, item: &Item) {
+    if let Some(existing) = items.iter_mut().find(|i| i.name == item.name) {
+        existing.quantity += item.quantity;
+    } else {
+        items.push(item.clone());
+    }
+}
+
+fn write_items(path: &str, items: &[Item]) -> io::Result<()> {
+    let mut writer = csv::Writer::from_path(path)?;
+    for item in items {
+        writer.serialize(item).map_err(csv_err_to_io)?;
+    }
+    writer.flush()
+}
+
+fn csv_err_to_io(err: csv::Error) -> io::Error {
+    io::Error::other(err)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    static COUNTER: AtomicU32 = AtomicU32::new(0);
+
+    fn temp_csv_path() -> PathBuf {
+        let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+        std::env::temp_dir().join(format!(
+            "inventory-tracker-test-{}-{n}.csv",
+            std::process::id()
+        ))
+    }
+
+    #[test]
+    fn round_trips_a_name_with_a_comma_and_a_quote() {
+        let path = temp_csv_path();
+        let items = vec![Item {
+            name: "1/4\", hex bolts".to_string(),
+            quantity: 3,
+        }];
+
+        append_items_to_csv(path.to_str().unwrap(), &items).unwrap();
+        let read_back = read_items(path.to_str().unwrap()).unwrap();
+
+        assert_eq!(read_back.len(), 1);
+        assert_eq!(read_back[0].name, "1/4\", hex bolts");
+        assert_eq!(read_back[0].quantity, 3);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn sums_quantities_for_duplicate_names_across_appends() {
+        let path = temp_csv_path();
+
+        append_items_to_csv(
+            path.to_str().unwrap(),
+            &[Item {
+                name: "hammer".to_string(),
+                quantity: 2,
+            }],
+        )
+        .unwrap();
+
+        let totals = append_items_to_csv(
+            path.to_str().unwrap(),
+            &[
+                Item {
+                    name: "hammer".to_string(),
+                    quantity: 1,
+                },
+                Item {
+                    name: "hammer".to_string(),
+                    quantity: 4,
+                },
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(totals.len(), 1);
+        assert_eq!(totals[0].quantity, 7);
+
+        let read_back = read_items(path.to_str().unwrap()).unwrap();
+        assert_eq!(read_back.len(), 1);
+        assert_eq!(read_back[0].quantity, 7);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -1,24 +1,48 @@
+use std::sync::Arc;
+
 use axum::{
-    extract::Form,
-    response::Html,
+    extract::{Multipart, Query, State},
+    http::header,
+    response::{Html, IntoResponse, Response},
     routing::{get, post},
     Router,
 };
 use serde::Deserialize;
-use std::{
-    fs::OpenOptions,
-    io::Write,
-};
 use tokio::net::TcpListener;
 
+mod encoding;
+mod inventory;
+mod parser;
+
+use encoding::Encoding;
+use inventory::Item;
+use parser::{HeuristicParser, Parser};
+
+#[derive(Clone)]
+struct AppState {
+    parser: Arc<dyn Parser>,
+}
+
 #[tokio::main]
 async fn main() {
-    // Build our router with two routes:
+    // Swap this out for `parser::LlmParser::new(endpoint, model)` to use a
+    // real local/remote model; it already falls back to the heuristic
+    // parser on failure.
+    let state = AppState {
+        parser: Arc::new(HeuristicParser),
+    };
+
+    // Build our router with four routes:
     // GET /      -> show the HTML form
     // POST /submit -> handle submitted text
+    // GET /export -> download the inventory as CSV
+    // GET /inventory -> view and filter the inventory
     let app = Router::new()
         .route("/", get(show_form))
-        .route("/submit", post(handle_submit));
+        .route("/submit", post(handle_submit))
+        .route("/export", get(export_csv))
+        .route("/inventory", get(list_inventory))
+        .with_state(state);
 
     // Bind to 0.0.0.0 so your phone on the LAN can reach it
     let listener = TcpListener::bind("0.0.0.0:3000")
@@ -31,20 +55,6 @@ async fn main() {
         .expect("server error");
 }
 
-// ----- Data types -----
-
-#[derive(Debug)]
-struct Item {
-    name: String,
-    quantity: i32,
-}
-
-#[derive(Deserialize)]
-struct InputForm {
-    // `name="text"` in the HTML form must match this field name
-    text: String,
-}
-
 // ----- Handlers -----
 
 async fn show_form() -> Html<&'static str> {
@@ -58,33 +68,123 @@ async fn show_form() -> Html<&'static str> {
   </head>
   <body style="font-family: sans-serif; padding: 1rem;">
     <h1>Inventory Inbox</h1>
-    <form method="post" action="/submit">
+    <form method="post" action="/submit" enctype="multipart/form-data">
       <label for="text">Speak or paste your message:</label><br>
       <textarea id="text" name="text" rows="8" cols="40" style="width: 100%;"></textarea><br><br>
+      <label for="csv_file">...or upload a CSV export:</label><br>
+      <input id="csv_file" type="file" name="csv_file" accept=".csv,text/csv"><br><br>
+      <label for="document">...or upload a plain-text or CSV document (PDF extraction isn't implemented yet):</label><br>
+      <input id="document" type="file" name="document"><br><br>
+      <label for="encoding">Text encoding:</label><br>
+      <select id="encoding" name="encoding">
+        <option value="utf8" selected>UTF-8</option>
+        <option value="cp437">CP437</option>
+      </select><br><br>
       <button type="submit">Submit</button>
     </form>
+    <p><a href="/export">Export inventory as CSV</a> &middot; <a href="/inventory">View inventory</a></p>
   </body>
 </html>"#)
 }
 
-async fn handle_submit(Form(input): Form<InputForm>) -> Html<String> {
-    // This is where your LLM will eventually live.
-    let items = fake_llm_parse(&input.text);
+async fn handle_submit(State(state): State<AppState>, mut multipart: Multipart) -> Html<String> {
+    // Buffer every field first so the encoding field (which can arrive in
+    // any order) is known before we decode the text/document fields.
+    let mut fields: Vec<(String, Option<String>, axum::body::Bytes)> = Vec::new();
+    loop {
+        let field = match multipart.next_field().await {
+            Ok(Some(field)) => field,
+            Ok(None) => break,
+            Err(e) => {
+                eprintln!("Failed to read multipart field: {e}");
+                break;
+            }
+        };
 
-    // Append items to a CSV file as a stub for "saving inventory".
-    if let Err(e) = append_items_to_csv("inventory.csv", &items) {
-        eprintln!("Failed to write CSV: {e}");
+        let field_name = field.name().unwrap_or("").to_string();
+        let content_type = field.content_type().map(str::to_string);
+        match field.bytes().await {
+            Ok(bytes) => fields.push((field_name, content_type, bytes)),
+            Err(e) => eprintln!("Failed to read '{field_name}' field: {e}"),
+        }
     }
 
+    let encoding = fields
+        .iter()
+        .find(|(name, _, _)| name == "encoding")
+        .map(|(_, _, bytes)| Encoding::from_form_value(&String::from_utf8_lossy(bytes)))
+        .unwrap_or_default();
+
+    let mut items: Vec<Item> = Vec::new();
+    for (field_name, content_type, bytes) in &fields {
+        if bytes.is_empty() {
+            continue;
+        }
+
+        match field_name.as_str() {
+            // A pasted-text submission goes through the configured parser.
+            "text" => {
+                let raw = encoding.encode(bytes);
+                match state.parser.parse(&raw).await {
+                    Ok(parsed) => items.extend(parsed),
+                    Err(e) => eprintln!("Failed to parse submission: {e}"),
+                }
+            }
+            // A CSV export is already structured, so deserialize it directly.
+            "csv_file" => {
+                let mut reader = csv::Reader::from_reader(bytes.as_ref());
+                for record in reader.deserialize::<Item>() {
+                    match record {
+                        Ok(item) => items.push(item),
+                        Err(e) => eprintln!("Failed to parse uploaded CSV row: {e}"),
+                    }
+                }
+            }
+            // Only plain-text and CSV-as-text documents are supported right
+            // now; the accepted one is parsed the same way a pasted message
+            // would be.
+            //
+            // TODO: extract text from PDFs (and other binary formats) once
+            // there's a text-extraction dependency to lean on; until then,
+            // is_plain_text rejects anything we can't safely decode instead
+            // of feeding raw binary into the parser and corrupting
+            // inventory.csv.
+            "document" => {
+                if !is_plain_text(content_type.as_deref(), bytes) {
+                    eprintln!(
+                        "Skipping document upload: only plain-text/CSV documents are supported (PDF extraction is not implemented yet)"
+                    );
+                    continue;
+                }
+                let raw = encoding.encode(bytes);
+                match state.parser.parse(&raw).await {
+                    Ok(parsed) => items.extend(parsed),
+                    Err(e) => eprintln!("Failed to parse document: {e}"),
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // Fold the parsed items into the CSV-backed inventory, summing
+    // quantities for names that already have stock on hand.
+    let totals = match inventory::append_items_to_csv("inventory.csv", &items) {
+        Ok(totals) => totals,
+        Err(e) => {
+            eprintln!("Failed to write CSV: {e}");
+            items
+        }
+    };
+
     // TODO: send labels to Zebra printer here.
 
-    // Render a simple confirmation page listing what we parsed.
+    // Render a simple confirmation page listing the running totals.
     let mut html = String::new();
     html.push_str("<!doctype html><html><head><meta charset=\"utf-8\"><title>Inventory Saved</title>");
     html.push_str("<meta name=\"viewport\" content=\"width=device-width, initial-scale=1.0\"></head><body style=\"font-family: sans-serif; padding: 1rem;\">");
     html.push_str("<h1>Parsed Items</h1><ul>");
 
-    for item in &items {
+    for item in &totals {
         html.push_str(&format!(
             "<li>{} &times; {}</li>",
             item.quantity, html_escape(&item.name)
@@ -98,57 +198,96 @@ async fn handle_submit(Form(input): Form<InputForm>) -> Html<String> {
     Html(html)
 }
 
-// ----- "LLM" stub -----
-
-/// For now, this is a fake “LLM parser” so you can test the flow.
-/// Replace this with a real local LLM call later.
-fn fake_llm_parse(raw: &str) -> Vec<Item> {
-    // Extremely dumb parser:
-    // - split on newlines
-    // - treat a leading number as quantity, rest as name
-    // Example input lines:
-    // "3 boxes of screws"
-    // "2x paint brush"
-    // "hammer"  (defaults to quantity = 1)
-    raw.lines()
-        .map(str::trim)
-        .filter(|line| !line.is_empty())
-        .map(|line| {
-            // Try to parse a leading integer
-            let mut parts = line.split_whitespace();
-            let first = parts.next().unwrap_or("");
-
-            if let Ok(qty) = first.trim_end_matches(|c: char| !c.is_ascii_digit()).parse::<i32>() {
-                let name = parts.collect::<Vec<_>>().join(" ");
-                Item {
-                    name: if name.is_empty() { line.to_string() } else { name },
-                    quantity: qty,
-                }
-            } else {
-                Item {
-                    name: line.to_string(),
-                    quantity: 1,
-                }
-            }
-        })
-        .collect()
+async fn export_csv() -> Response {
+    let items = match inventory::read_items("inventory.csv") {
+        Ok(items) => items,
+        Err(e) => {
+            eprintln!("Failed to read CSV for export: {e}");
+            Vec::new()
+        }
+    };
+
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    for item in &items {
+        if let Err(e) = writer.serialize(item) {
+            eprintln!("Failed to serialize item for export: {e}");
+        }
+    }
+    let buf = writer.into_inner().unwrap_or_default();
+
+    (
+        [
+            (header::CONTENT_TYPE, "text/csv"),
+            (
+                header::CONTENT_DISPOSITION,
+                "attachment; filename=\"inventory.csv\"",
+            ),
+        ],
+        buf,
+    )
+        .into_response()
 }
 
-// ----- CSV stub -----
+#[derive(Deserialize)]
+struct InventoryQuery {
+    q: Option<String>,
+    min_qty: Option<i32>,
+}
 
-fn append_items_to_csv(path: &str, items: &[Item]) -> std::io::Result<()> {
-    let mut file = OpenOptions::new()
-        .create(true)
-        .append(true)
-        .open(path)?;
+async fn list_inventory(Query(query): Query<InventoryQuery>) -> Html<String> {
+    let items = match inventory::read_items("inventory.csv") {
+        Ok(items) => items,
+        Err(e) => {
+            eprintln!("Failed to read CSV for inventory view: {e}");
+            Vec::new()
+        }
+    };
 
-    for item in items {
-        // Very naive CSV: quantity,name
-        // (No escaping of commas/quotes; good enough for version 0.)
-        writeln!(file, "{},{}", item.quantity, item.name)?;
+    let needle = query.q.as_deref().unwrap_or("").to_lowercase();
+    let filtered: Vec<&Item> = items
+        .iter()
+        .filter(|item| item.name.to_lowercase().contains(&needle))
+        .filter(|item| query.min_qty.is_none_or(|min| item.quantity >= min))
+        .collect();
+
+    let mut html = String::new();
+    html.push_str("<!doctype html><html><head><meta charset=\"utf-8\"><title>Inventory</title>");
+    html.push_str("<meta name=\"viewport\" content=\"width=device-width, initial-scale=1.0\"></head><body style=\"font-family: sans-serif; padding: 1rem;\">");
+    html.push_str("<h1>Inventory</h1>");
+    html.push_str(&format!(
+        r#"<form method="get" action="/inventory">
+      <input type="text" name="q" placeholder="Search by name" value="{}">
+      <input type="number" name="min_qty" placeholder="Min qty" value="{}">
+      <button type="submit">Filter</button>
+    </form>"#,
+        html_escape(query.q.as_deref().unwrap_or("")),
+        query.min_qty.map(|q| q.to_string()).unwrap_or_default(),
+    ));
+    html.push_str("<table border=\"1\" cellpadding=\"4\"><tr><th>Name</th><th>Quantity</th></tr>");
+    for item in &filtered {
+        html.push_str(&format!(
+            "<tr><td>{}</td><td>{}</td></tr>",
+            html_escape(&item.name),
+            item.quantity
+        ));
     }
+    html.push_str("</table>");
+    html.push_str(r#"<p><a href="/">Back</a></p>"#);
+    html.push_str("</body></html>");
 
-    Ok(())
+    Html(html)
+}
+
+/// Whitelist of uploaded "document" fields we can safely decode and parse
+/// as text. Everything else (PDFs, images, and any other binary format we
+/// don't yet extract text from) is rejected rather than lossy-decoded,
+/// which would otherwise write garbage rows into `inventory.csv`.
+fn is_plain_text(content_type: Option<&str>, bytes: &[u8]) -> bool {
+    match content_type {
+        Some("text/plain") | Some("text/csv") => true,
+        Some(_) => false,
+        None => std::str::from_utf8(bytes).is_ok() && !bytes.contains(&0),
+    }
 }
 
 // ----- Small helper -----
@@ -157,4 +296,6 @@ fn html_escape(s: &str) -> String {
     s.replace('&', "&amp;")
         .replace('<', "&lt;")
         .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
 }
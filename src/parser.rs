@@ -0,0 +1,148 @@
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::inventory::Item;
+
+/// Turns a free-text inbox message into structured inventory items.
+#[async_trait]
+pub trait Parser: Send + Sync {
+    async fn parse(&self, raw: &str) -> anyhow::Result<Vec<Item>>;
+}
+
+/// The original line-based parser, kept around as a dependency-free
+/// fallback for when the LLM backend is unavailable.
+pub struct HeuristicParser;
+
+#[async_trait]
+impl Parser for HeuristicParser {
+    async fn parse(&self, raw: &str) -> anyhow::Result<Vec<Item>> {
+        Ok(heuristic_parse(raw))
+    }
+}
+
+/// Extremely dumb parser:
+/// - split on newlines
+/// - treat a leading number as quantity, rest as name
+///
+/// Example input lines:
+/// "3 boxes of screws"
+/// "2x paint brush"
+/// "hammer"  (defaults to quantity = 1)
+pub fn heuristic_parse(raw: &str) -> Vec<Item> {
+    raw.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            let mut parts = line.split_whitespace();
+            let first = parts.next().unwrap_or("");
+
+            if let Ok(qty) = first
+                .trim_end_matches(|c: char| !c.is_ascii_digit())
+                .parse::<i32>()
+            {
+                let name = parts.collect::<Vec<_>>().join(" ");
+                Item {
+                    name: if name.is_empty() { line.to_string() } else { name },
+                    quantity: qty,
+                }
+            } else {
+                Item {
+                    name: line.to_string(),
+                    quantity: 1,
+                }
+            }
+        })
+        .collect()
+}
+
+/// Calls out to a chat/completion model (local or remote, e.g. an
+/// Ollama-compatible `/api/generate` endpoint) and asks it to return
+/// strict JSON describing the items mentioned in a message.
+///
+/// Not wired up by default (see `main`'s `AppState` construction) until an
+/// endpoint/model is chosen at deployment time, so this is allowed to be
+/// unused from the binary's own perspective.
+#[allow(dead_code)]
+pub struct LlmParser {
+    client: reqwest::Client,
+    endpoint: String,
+    model: String,
+}
+
+#[allow(dead_code)]
+impl LlmParser {
+    pub fn new(endpoint: impl Into<String>, model: impl Into<String>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            endpoint: endpoint.into(),
+            model: model.into(),
+        }
+    }
+
+    async fn parse_with_llm(&self, raw: &str) -> anyhow::Result<Vec<Item>> {
+        let prompt = format!(
+            "Extract the inventory items mentioned below. Respond with ONLY strict JSON \
+             in the form [{{\"name\": \"...\", \"quantity\": ...}}], no prose, no markdown.\n\n{raw}"
+        );
+
+        let response = self
+            .client
+            .post(&self.endpoint)
+            .json(&GenerateRequest {
+                model: &self.model,
+                prompt,
+                stream: false,
+            })
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<GenerateResponse>()
+            .await?;
+
+        let llm_items: Vec<LlmItem> = serde_json::from_str(response.response.trim())?;
+
+        Ok(llm_items
+            .into_iter()
+            .map(|i| Item {
+                name: i.name,
+                quantity: i.quantity,
+            })
+            .collect())
+    }
+}
+
+#[async_trait]
+impl Parser for LlmParser {
+    async fn parse(&self, raw: &str) -> anyhow::Result<Vec<Item>> {
+        // Fall back to the heuristic parser rather than surfacing an
+        // error to the user whenever the model call or its JSON is bad.
+        match self.parse_with_llm(raw).await {
+            Ok(items) => Ok(items),
+            Err(e) => {
+                eprintln!("LLM parse failed, falling back to heuristic parser: {e}");
+                Ok(heuristic_parse(raw))
+            }
+        }
+    }
+}
+
+#[allow(dead_code)]
+#[derive(Deserialize)]
+struct LlmItem {
+    name: String,
+    quantity: i32,
+}
+
+#[allow(dead_code)]
+#[derive(Serialize)]
+struct GenerateRequest<'a> {
+    model: &'a str,
+    prompt: String,
+    stream: bool,
+}
+
+#[allow(dead_code)]
+#[derive(Deserialize)]
+struct GenerateResponse {
+    response: String,
+}